@@ -1,5 +1,6 @@
 use clap::Parser;
 use std::fs::OpenOptions;
+use std::os::unix::fs::OpenOptionsExt;
 use io_uring::{opcode, types, IoUring};
 use std::os::fd::AsRawFd;
 use std::collections::VecDeque;
@@ -7,10 +8,166 @@ use libc::iovec;
 use libc::{rlimit, getrlimit, RLIMIT_MEMLOCK};
 use std::sync::{Arc, atomic::{AtomicU64, Ordering, AtomicBool}};
 use std::io::{Write};
+use std::alloc::{alloc_zeroed, dealloc, Layout};
+use rand::seq::SliceRandom;
+use sha2::{Digest as Sha2DigestTrait, Sha256};
 
 const TAG_READ: u64 = 0;
 const TAG_WRITE: u64 = 1;
 
+/// Buffer alignment required by O_DIRECT: must cover alignment of the
+/// buffer address, the file offset, and the transfer length.
+const DIRECT_ALIGN: usize = 4096;
+
+/// Set by the SIGUSR1/SIGINFO handler, checked in each worker's completion
+/// loop so a transfer-status report can be printed without interrupting the
+/// copy. Signal handlers can't capture state, so this has to be a static.
+static STATUS_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn request_status_report(_signum: libc::c_int) {
+    STATUS_REQUESTED.store(true, Ordering::Relaxed);
+}
+
+/// Mirrors GNU dd: SIGUSR1 (and SIGINFO on BSD/macOS, where typing Ctrl-T
+/// sends it) triggers an immediate transfer-status report to stderr.
+fn install_status_signal_handlers() {
+    unsafe {
+        libc::signal(libc::SIGUSR1, request_status_report as libc::sighandler_t);
+        #[cfg(any(target_os = "macos", target_os = "freebsd", target_os = "netbsd", target_os = "openbsd", target_os = "dragonfly"))]
+        libc::signal(libc::SIGINFO, request_status_report as libc::sighandler_t);
+    }
+}
+
+/// Parses a size with an optional decimal (kB/MB/GB, powers of 1000) or
+/// binary (K/KiB/M/MiB/G/GiB, powers of 1024) suffix, mirroring the
+/// suffixes accepted by GNU dd / uucore's `parse_size`.
+fn parse_size(s: &str) -> Result<u64, String> {
+    let s = s.trim();
+    let split_at = s.find(|c: char| c.is_alphabetic()).unwrap_or(s.len());
+    let (num_part, suffix) = s.split_at(split_at);
+    let value: f64 = num_part.parse().map_err(|_| format!("invalid size `{}`", s))?;
+    let multiplier: f64 = match suffix {
+        "" | "b" => 1.0,
+        "kB" => 1_000.0,
+        "K" | "KiB" => 1024.0,
+        "MB" => 1_000_000.0,
+        "M" | "MiB" => 1024.0 * 1024.0,
+        "GB" => 1_000_000_000.0,
+        "G" | "GiB" => 1024.0 * 1024.0 * 1024.0,
+        other => return Err(format!("unknown size suffix `{}`", other)),
+    };
+    Ok((value * multiplier).round() as u64)
+}
+
+fn parse_block_size(s: &str) -> Result<usize, String> {
+    parse_size(s).map(|v| v as usize)
+}
+
+/// `--count` is normally a block count, but dd users frequently want to
+/// express it as a byte count instead (e.g. `--count=10MiB`). Keep the two
+/// forms distinct until `arg_parse` knows the block size needed to convert
+/// a byte count into whole blocks.
+#[derive(Debug, Clone, Copy)]
+enum CountSpec {
+    Blocks(u64),
+    Bytes(u64),
+}
+
+fn parse_count(s: &str) -> Result<CountSpec, String> {
+    if s.chars().any(|c| c.is_alphabetic()) {
+        parse_size(s).map(CountSpec::Bytes)
+    } else {
+        s.parse::<u64>().map(CountSpec::Blocks).map_err(|_| format!("invalid count `{}`", s))
+    }
+}
+
+/// One of dd's `conv=` data conversions, applied to a buffer after the
+/// read completes but before the write is submitted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConvMode {
+    /// Swap each pair of adjacent bytes.
+    Swab,
+    /// Lowercase ASCII bytes.
+    Lcase,
+    /// Uppercase ASCII bytes.
+    Ucase,
+    /// Zero-pad short final reads up to the full block size.
+    Sync,
+    /// Skip read errors instead of aborting the copy.
+    NoError,
+}
+
+fn parse_conv(s: &str) -> Result<ConvMode, String> {
+    match s {
+        "swab" => Ok(ConvMode::Swab),
+        "lcase" => Ok(ConvMode::Lcase),
+        "ucase" => Ok(ConvMode::Ucase),
+        "sync" => Ok(ConvMode::Sync),
+        "noerror" => Ok(ConvMode::NoError),
+        other => Err(format!("unknown conv mode `{}`", other)),
+    }
+}
+
+/// Checksum algorithm for `--hash`/`--verify`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashAlgo {
+    Md5,
+    Crc32,
+    Sha256,
+}
+
+fn parse_hash_algo(s: &str) -> Result<HashAlgo, String> {
+    match s {
+        "md5" => Ok(HashAlgo::Md5),
+        "crc32" => Ok(HashAlgo::Crc32),
+        "sha256" => Ok(HashAlgo::Sha256),
+        other => Err(format!("unknown hash algorithm `{}`", other)),
+    }
+}
+
+fn hash_algo_name(algo: HashAlgo) -> &'static str {
+    match algo {
+        HashAlgo::Md5 => "md5",
+        HashAlgo::Crc32 => "crc32",
+        HashAlgo::Sha256 => "sha256",
+    }
+}
+
+/// Rolling checksum state for one of the supported `--hash` algorithms.
+enum ChecksumState {
+    Md5(md5::Context),
+    Crc32(crc32fast::Hasher),
+    Sha256(Sha256),
+}
+
+impl ChecksumState {
+    fn new(algo: HashAlgo) -> Self {
+        match algo {
+            HashAlgo::Md5 => ChecksumState::Md5(md5::Context::new()),
+            HashAlgo::Crc32 => ChecksumState::Crc32(crc32fast::Hasher::new()),
+            HashAlgo::Sha256 => ChecksumState::Sha256(Sha256::new()),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            ChecksumState::Md5(ctx) => ctx.consume(data),
+            ChecksumState::Crc32(hasher) => hasher.update(data),
+            ChecksumState::Sha256(hasher) => Sha2DigestTrait::update(hasher, data),
+        }
+    }
+
+    fn finalize_hex(self) -> String {
+        match self {
+            ChecksumState::Md5(ctx) => format!("{:x}", ctx.compute()),
+            ChecksumState::Crc32(hasher) => format!("{:08x}", hasher.finalize()),
+            ChecksumState::Sha256(hasher) => Sha2DigestTrait::finalize(hasher)
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect(),
+        }
+    }
+}
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
@@ -23,13 +180,15 @@ pub struct Args {
     #[arg(long="of")]
     output_file: String,
 
-    /// Block size
-    #[arg(long="bs", default_value_t = 4096)]
+    /// Block size. Accepts decimal (kB/MB/GB) and binary (K/KiB/M/MiB/G/GiB) suffixes.
+    #[arg(long="bs", default_value_t = 4096, value_parser = parse_block_size)]
     block_size: usize,
 
     /// Number of blocks to copy. If not specified, the entire file will be copied.
-    #[arg(short, long)]
-    count: Option<u64>,
+    /// Accepts a plain block count, or a byte count with a size suffix
+    /// (e.g. `--count=10MiB`), which is translated to whole blocks.
+    #[arg(short, long, value_parser = parse_count)]
+    count: Option<CountSpec>,
 
     /// Input file seek offset in blocks
     #[arg(long="is", default_value_t = 0)]
@@ -50,7 +209,33 @@ pub struct Args {
     /// Show progress during the operation
     #[arg(long, default_value_t = false)]
     progress: bool,
-    
+
+    /// Bypass the page cache with O_DIRECT. Requires block size and seek
+    /// offsets to be multiples of the logical block size (4096 bytes).
+    #[arg(long, default_value_t = false)]
+    direct: bool,
+
+    /// Comma-separated data conversions: swab, lcase, ucase, sync, noerror.
+    #[arg(long, value_delimiter = ',', value_parser = parse_conv)]
+    conv: Vec<ConvMode>,
+
+    /// Number of worker threads, each with its own io_uring, copying disjoint
+    /// shuffled chunks of the file in parallel. Defaults to a single-threaded copy.
+    #[arg(long, default_value_t = 1)]
+    jobs: u64,
+
+    /// Compute a checksum of the copied range of the output file (the transfer
+    /// size, starting at the output seek offset) once the copy completes,
+    /// printed to stderr alongside the byte count: md5, crc32, or sha256.
+    #[arg(long, value_parser = parse_hash_algo)]
+    hash: Option<HashAlgo>,
+
+    /// After computing `--hash`, read the output file back a second time and
+    /// confirm its checksum is unchanged. Implies `--hash=md5` if `--hash` is
+    /// not given.
+    #[arg(long, default_value_t = false)]
+    verify: bool,
+
 }
 
 pub struct ArgData {
@@ -63,6 +248,51 @@ pub struct ArgData {
     pub ring_size: u32,
     pub num_buffers: u64,
     pub progress: bool,
+    pub direct: bool,
+    pub conv: Vec<ConvMode>,
+    pub jobs: u64,
+    pub hash: Option<HashAlgo>,
+    pub verify: bool,
+}
+
+/// Owns a heap allocation aligned to `align` bytes, freeing it on drop.
+/// Used for O_DIRECT transfers, which require the buffer address itself
+/// to be aligned in addition to the file offset and transfer length.
+struct AlignedBuf {
+    ptr: *mut u8,
+    len: usize,
+    layout: Layout,
+}
+
+impl AlignedBuf {
+    fn new(len: usize, align: usize) -> std::io::Result<Self> {
+        let layout = Layout::from_size_align(len, align)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e.to_string()))?;
+        let ptr = unsafe { alloc_zeroed(layout) };
+        if ptr.is_null() {
+            return Err(std::io::Error::new(std::io::ErrorKind::OutOfMemory, "failed to allocate aligned buffer"));
+        }
+        Ok(Self { ptr, len, layout })
+    }
+}
+
+impl std::ops::Deref for AlignedBuf {
+    type Target = [u8];
+    fn deref(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+    }
+}
+
+impl std::ops::DerefMut for AlignedBuf {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        unsafe { std::slice::from_raw_parts_mut(self.ptr, self.len) }
+    }
+}
+
+impl Drop for AlignedBuf {
+    fn drop(&mut self) {
+        unsafe { dealloc(self.ptr, self.layout) };
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -71,12 +301,42 @@ struct RWMetadata {
     size: u64,
 }
 
-fn open_file(path: &str) -> std::io::Result<std::fs::File> {
-    OpenOptions::new()
-        .read(true)
-        .write(true)
-        .create(true)
-        .open(path)
+fn open_file(path: &str, direct: bool) -> std::io::Result<std::fs::File> {
+    let mut opts = OpenOptions::new();
+    opts.read(true).write(true).create(true);
+    if direct {
+        opts.custom_flags(libc::O_DIRECT);
+    }
+    opts.open(path)
+}
+
+/// O_DIRECT requires the buffer address, file offset, and transfer length
+/// to all be aligned to the logical block size. Check the offsets we were
+/// given up front so misalignment fails with a clear error instead of an
+/// opaque EINVAL from the kernel mid-copy. `total_size` must also be a
+/// multiple of `block_size`: otherwise the final chunk's read/write would be
+/// shorter than `block_size` and fail the transfer-length alignment rule the
+/// same way, just on the last block instead of the first.
+fn validate_direct_alignment(block_size: u64, ibase: u64, obase: u64, total_size: u64) -> std::io::Result<()> {
+    if block_size % DIRECT_ALIGN as u64 != 0 || ibase % DIRECT_ALIGN as u64 != 0 || obase % DIRECT_ALIGN as u64 != 0 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "--direct requires block size ({}) and seek offsets (input={}, output={}) to be multiples of {} bytes",
+                block_size, ibase, obase, DIRECT_ALIGN
+            ),
+        ));
+    }
+    if total_size % block_size != 0 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "--direct requires the transfer size ({}) to be a multiple of the block size ({}); the final block would be a short, unaligned read/write",
+                total_size, block_size
+            ),
+        ));
+    }
+    Ok(())
 }
 
 #[inline]
@@ -103,6 +363,22 @@ fn get_memlock_limit() -> Option<u64> {
     }
 }
 
+/// Swaps each adjacent pair of bytes in place (dd's `conv=swab`). A
+/// trailing odd byte, if any, is left untouched.
+fn swab_buffer(buf: &mut [u8]) {
+    let pairs = buf.len() / 2;
+    for i in 0..pairs {
+        buf.swap(i * 2, i * 2 + 1);
+    }
+}
+
+/// Prints a dd-style transfer-status report in response to SIGUSR1/SIGINFO.
+fn print_transfer_status(bytes: u64, start: std::time::Instant) {
+    let elapsed = start.elapsed().as_secs_f64();
+    let mb_per_sec = if elapsed > 0.0 { (bytes as f64 / 1_000_000.0) / elapsed } else { 0.0 };
+    eprintln!("\n{} bytes copied, {:.1} s, {:.2} MB/s", bytes, elapsed, mb_per_sec);
+}
+
 fn print_status(cur_bytes: u64, total_size: u64) {
     if total_size > 0 {
         let percent = (cur_bytes as f64 / total_size as f64) * 100.0;
@@ -113,32 +389,92 @@ fn print_status(cur_bytes: u64, total_size: u64) {
     std::io::stdout().flush().unwrap();
 }
 
-pub fn execute_dd(arg_data: &ArgData) -> std::io::Result<u64> {
+/// Minimum worker chunk size, in blocks, used by `--jobs`.
+const MIN_CHUNK_BLOCKS: u64 = 128;
+/// Maximum worker chunk size, in blocks, used by `--jobs`.
+const MAX_CHUNK_BLOCKS: u64 = 4096;
+
+/// Splits `num_blocks` into contiguous `(start_block, end_block)` chunk runs
+/// sized so that each of `jobs` workers sees many chunks, then shuffles the
+/// chunk list so a worker gets a spread of regions rather than one monolithic
+/// extent (avoids one thread stalling on a slow region while others idle).
+fn build_chunks(num_blocks: u64, jobs: u64) -> Vec<(u64, u64)> {
+    let chunk_blocks = (num_blocks / (jobs * 64)).clamp(MIN_CHUNK_BLOCKS, MAX_CHUNK_BLOCKS);
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < num_blocks {
+        let end = (start + chunk_blocks).min(num_blocks);
+        chunks.push((start, end));
+        start = end;
+    }
+    chunks.shuffle(&mut rand::rng());
+    chunks
+}
+
+/// Expands a `(start_block, end_block)` chunk into the `(offset, size)` read
+/// list `run_copy_worker` consumes, using the same input base and total size
+/// accounting as the rest of the copy.
+fn chunk_to_reads(chunk: (u64, u64), bs: u64, ibase: u64, total_size: u64) -> Vec<(u64, u64)> {
+    let (start, end) = chunk;
+    (start..end)
+        .map(|block| {
+            let rel_offset = block * bs;
+            let size = bs.min(total_size - rel_offset);
+            (ibase + rel_offset, size)
+        })
+        .collect()
+}
+
+/// Everything one worker needs to copy its assigned reads through its own
+/// ring, independent of every other worker.
+struct CopyWorkerArgs {
+    ifile: std::fs::File,
+    ofile: std::fs::File,
+    bs: u64,
+    ibase: u64,
+    obase: u64,
+    reads: VecDeque<(u64, u64)>,
+    num_buffers: u64,
+    direct: bool,
+    conv: Vec<ConvMode>,
+    /// Divides the process-wide memlock limit fairly when multiple workers
+    /// each register their own buffers concurrently.
+    memlock_divisor: u64,
+    status_byte_count: Arc<AtomicU64>,
+    start_time: std::time::Instant,
+}
+
+/// Full/partial read and write record counts and total bytes written,
+/// tracked the way GNU dd reports them in its final "records in/out" summary.
+#[derive(Default)]
+struct WorkerStats {
+    bytes_written: u64,
+    full_reads: u64,
+    partial_reads: u64,
+    full_writes: u64,
+    partial_writes: u64,
+}
+
+/// Copies `reads` from `ifile` to `ofile` through a dedicated `IoUring`,
+/// buffer pool, and pair of registered files. Used directly for a
+/// single-threaded copy, and once per thread for `--jobs N`.
+fn run_copy_worker(w: CopyWorkerArgs) -> std::io::Result<WorkerStats> {
+    let CopyWorkerArgs { ifile, ofile, bs, ibase, obase, reads, num_buffers, direct, conv, memlock_divisor, status_byte_count, start_time } = w;
     let mut uring = IoUring::new(256)?;
-    let ifile = &arg_data.ifile;
-    let ofile = &arg_data.ofile;
-    let bs = arg_data.block_size as u64;
-    let ibase = arg_data.iseek * bs;
-    let obase = arg_data.oseek * bs;
-    let num_buffers = arg_data.num_buffers;
+    let num_blocks = reads.len() as u64;
+    let mut cur_blocks = 0;
     let mut free_bufs: VecDeque<u64> = (0..num_buffers).collect();
     let default_metadata = RWMetadata { offset: 0, size: 0 };
     let mut metadata = vec![default_metadata; (num_buffers * 2) as usize];
-    let file_len = ifile.metadata()?.len().checked_sub(ibase)
-        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::Other, "Invalid input seek offset"))?;
-    let total_size = match arg_data.count {
-        Some(c) => file_len.min(c * bs),
-        None => {
-            file_len
-        }
-    };
-    let mut cur_blocks = 0;
-    let num_blocks = if total_size % bs == 0 {total_size / bs} else {total_size / bs + 1};
-    let mut cur_bytes = 0;
-    let mut to_reads: VecDeque<(u64, u64)> = VecDeque::new(); // offset & size. Used for reading remaining data
+    // Bytes already assembled into each buffer for its in-flight logical
+    // block; a short read that isn't EOF re-reads into the same buffer
+    // past this offset instead of being flushed as a separate fragment, so
+    // every conv transform and write sees one contiguous block.
+    let mut read_filled: Vec<u64> = vec![0; num_buffers as usize];
+    let mut to_reads = reads;
 
     let max_buf_size = match get_memlock_limit() {
-        Some(limit) => limit,
+        Some(limit) => limit / memlock_divisor.max(1),
         None => {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::Other,
@@ -147,15 +483,16 @@ pub fn execute_dd(arg_data: &ArgData) -> std::io::Result<u64> {
         }
     };
 
-    let registered_num_buffers = if num_buffers * bs as u64 >= max_buf_size {
-        (max_buf_size / bs as u64) as u64 - 1
+    let registered_num_buffers = if num_buffers * bs >= max_buf_size {
+        (max_buf_size / bs).saturating_sub(1)
     } else {
         num_buffers
     };
 
-    let mut bufs: Vec<Box<[u8]>> = (0..num_buffers)
-        .map(|_| vec![0u8; bs as usize].into_boxed_slice())
-        .collect();
+    let buf_align = if direct { DIRECT_ALIGN } else { 1 };
+    let mut bufs: Vec<AlignedBuf> = (0..num_buffers)
+        .map(|_| AlignedBuf::new(bs as usize, buf_align))
+        .collect::<std::io::Result<_>>()?;
 
     let iovecs: Vec<iovec> = bufs.iter()
         .take(registered_num_buffers as usize)
@@ -172,41 +509,21 @@ pub fn execute_dd(arg_data: &ArgData) -> std::io::Result<u64> {
         uring.submitter().register_files(&fds)?;
     }
 
-    let status_byte_count = Arc::new(AtomicU64::new(0));
-    let status_byte_count_clone = Arc::clone(&status_byte_count);
-    let stop_flag = Arc::new(AtomicBool::new(false));
-    let stop_flag_clone = Arc::clone(&stop_flag);
-    let handle = if !arg_data.progress {
-        None
-    } else {
-        Some(std::thread::spawn(move || {
-            while !stop_flag_clone.load(Ordering::Relaxed) {
-                let bytes_copied = status_byte_count_clone.load(Ordering::Relaxed);
-                print_status(bytes_copied, total_size);
-                std::thread::sleep(std::time::Duration::from_secs(1));
-            }
-        }))
-    };
+    let conv_swab = conv.contains(&ConvMode::Swab);
+    let conv_lcase = conv.contains(&ConvMode::Lcase);
+    let conv_ucase = conv.contains(&ConvMode::Ucase);
+    let conv_sync = conv.contains(&ConvMode::Sync);
+    let conv_noerror = conv.contains(&ConvMode::NoError);
+
+    let mut stats = WorkerStats::default();
 
     // main loop
     while cur_blocks < num_blocks {
-        while !free_bufs.is_empty() && (!to_reads.is_empty() || cur_bytes < total_size) {
+        while !free_bufs.is_empty() && !to_reads.is_empty() {
             if uring.submission().is_full() {
                 uring.submit()?;
             }
-            let (roffset, rsize);
-            if !to_reads.is_empty() {
-                (roffset, rsize) = to_reads.pop_front().unwrap();
-            } else if cur_bytes < total_size {
-                roffset = ibase + cur_bytes;
-                rsize = bs.min(total_size - cur_bytes);
-                cur_bytes += rsize;
-            } else {
-                return Err(std::io::Error::new(
-                    std::io::ErrorKind::Other,
-                    "No more data to read",
-                ));
-            }
+            let (roffset, rsize) = to_reads.pop_front().unwrap();
 
             let buf_idx = free_bufs.pop_front().unwrap();
             let buf = &mut bufs[buf_idx as usize];
@@ -238,40 +555,126 @@ pub fn execute_dd(arg_data: &ArgData) -> std::io::Result<u64> {
             let buf_idx = cqe.user_data() >> 1;
             let res = cqe.result();
             if op_type == TAG_READ {
-                if res < 0 {
+                if res < 0 && !conv_noerror {
                     eprintln!("Read operation failed: {}", res);
                     return Err(std::io::Error::from_raw_os_error(-res as i32));
                 }
-                let res = res as u64;
-                let read_metadata = &metadata[buf_idx as usize * 2];
-                // if res as u64 == 0 {
-                //     eprintln!("Read should not be zero here");
-                //     return 1;
-                // }
-                if res != read_metadata.size {
-                    assert!(res < read_metadata.size);
-                    let roffset = read_metadata.offset + res;
-                    let rsize = read_metadata.size - res;
-                    to_reads.push_back((roffset, rsize));
-                }
-                let woffset = obase + read_metadata.offset - ibase;
-                let user_data = buf_idx << 1 | TAG_WRITE;
-                let write_op = if buf_idx < registered_num_buffers {
-                    opcode::WriteFixed::new(
-                        types::Fixed(1),
-                        bufs[buf_idx as usize].as_ptr(),
-                        res as u32,
-                        buf_idx as u16,
-                    ).offset(woffset).build().user_data(user_data)
+                if res < 0 {
+                    // conv=noerror: log and skip the failed region instead of aborting.
+                    // Any bytes already assembled from an earlier short read on this
+                    // buffer are discarded too — the whole logical block is treated
+                    // as bad, matching dd's own noerror behavior.
+                    let read_metadata = metadata[buf_idx as usize * 2];
+                    eprintln!("Read operation failed at offset {}: {}, skipping (conv=noerror)", read_metadata.offset, res);
+                    read_filled[buf_idx as usize] = 0;
+                    stats.partial_reads += 1;
+                    if conv_sync {
+                        let size = read_metadata.size;
+                        bufs[buf_idx as usize][..size as usize].fill(0);
+                        let woffset = obase + read_metadata.offset - ibase;
+                        let user_data = buf_idx << 1 | TAG_WRITE;
+                        let write_op = if buf_idx < registered_num_buffers {
+                            opcode::WriteFixed::new(
+                                types::Fixed(1),
+                                bufs[buf_idx as usize].as_ptr(),
+                                size as u32,
+                                buf_idx as u16,
+                            ).offset(woffset).build().user_data(user_data)
+                        } else {
+                            opcode::Write::new(
+                                types::Fixed(1),
+                                bufs[buf_idx as usize].as_ptr(),
+                                size as u32,
+                            ).offset(woffset).build().user_data(user_data)
+                        };
+                        push_sqe(&mut uring, &write_op)?;
+                        metadata[buf_idx as usize * 2 + 1] = RWMetadata { offset: woffset, size };
+                    } else {
+                        // Region dropped entirely: nothing is written for it, so it
+                        // isn't a record out at all — only the failed read above
+                        // counts towards the records-in/records-out summary.
+                        cur_blocks += 1;
+                        free_bufs.push_back(buf_idx);
+                    }
                 } else {
-                    opcode::Write::new(
-                        types::Fixed(1),
-                        bufs[buf_idx as usize].as_ptr(),
-                        res as u32,
-                    ).offset(woffset).build().user_data(user_data)
-                };
-                push_sqe(&mut uring, &write_op)?;
-                metadata[buf_idx as usize * 2 + 1] = RWMetadata { offset: woffset, size: res };
+                    let res = res as u64;
+                    let read_metadata = metadata[buf_idx as usize * 2];
+                    let filled = read_filled[buf_idx as usize] + res;
+                    // `res == 0` is EOF: no more data is coming for this block, short
+                    // or not. Otherwise the block is only done once `filled` reaches
+                    // the full requested size — a short return that isn't EOF is a
+                    // genuine partial syscall read, re-read into the same buffer past
+                    // what's already there so the block stays one contiguous region
+                    // instead of being split into separately-written fragments.
+                    if res != 0 && filled < read_metadata.size {
+                        read_filled[buf_idx as usize] = filled;
+                        let roffset = read_metadata.offset + filled;
+                        let rsize = read_metadata.size - filled;
+                        let buf = &mut bufs[buf_idx as usize];
+                        let user_data = buf_idx << 1 | TAG_READ;
+                        let read_op = if buf_idx < registered_num_buffers {
+                            opcode::ReadFixed::new(
+                                types::Fixed(0),
+                                unsafe { buf.as_mut_ptr().add(filled as usize) },
+                                rsize as u32,
+                                buf_idx as u16,
+                            ).offset(roffset).build().user_data(user_data)
+                        } else {
+                            opcode::Read::new(
+                                types::Fixed(0),
+                                unsafe { buf.as_mut_ptr().add(filled as usize) },
+                                rsize as u32,
+                            ).offset(roffset).build().user_data(user_data)
+                        };
+                        push_sqe(&mut uring, &read_op)?;
+                    } else {
+                        read_filled[buf_idx as usize] = 0;
+                        if filled == bs {
+                            stats.full_reads += 1;
+                        } else {
+                            stats.partial_reads += 1;
+                        }
+                        let mut wsize = filled;
+                        if conv_swab || conv_lcase || conv_ucase || conv_sync {
+                            let buf = &mut bufs[buf_idx as usize];
+                            // The block is now fully assembled, so swab sees one
+                            // contiguous run of bytes and pairs them correctly
+                            // instead of risking a mis-paired split at whatever
+                            // offset an earlier short read happened to stop at.
+                            if conv_swab {
+                                swab_buffer(&mut buf[..filled as usize]);
+                            }
+                            if conv_lcase {
+                                buf[..filled as usize].make_ascii_lowercase();
+                            }
+                            if conv_ucase {
+                                buf[..filled as usize].make_ascii_uppercase();
+                            }
+                            if conv_sync && filled < bs {
+                                buf[filled as usize..bs as usize].fill(0);
+                                wsize = bs;
+                            }
+                        }
+                        let woffset = obase + read_metadata.offset - ibase;
+                        let user_data = buf_idx << 1 | TAG_WRITE;
+                        let write_op = if buf_idx < registered_num_buffers {
+                            opcode::WriteFixed::new(
+                                types::Fixed(1),
+                                bufs[buf_idx as usize].as_ptr(),
+                                wsize as u32,
+                                buf_idx as u16,
+                            ).offset(woffset).build().user_data(user_data)
+                        } else {
+                            opcode::Write::new(
+                                types::Fixed(1),
+                                bufs[buf_idx as usize].as_ptr(),
+                                wsize as u32,
+                            ).offset(woffset).build().user_data(user_data)
+                        };
+                        push_sqe(&mut uring, &write_op)?;
+                        metadata[buf_idx as usize * 2 + 1] = RWMetadata { offset: woffset, size: wsize };
+                    }
+                }
             } else { // TAG_WRITE
                 if res < 0 {
                     eprintln!("Write operation failed: {}", res);
@@ -280,6 +683,7 @@ pub fn execute_dd(arg_data: &ArgData) -> std::io::Result<u64> {
                 let res = res as u64;
                 let write_metadata = &metadata[buf_idx as usize * 2 + 1];
                 status_byte_count.fetch_add(res, Ordering::Relaxed);
+                stats.bytes_written += res;
                 if res != write_metadata.size {
                     assert!(res < write_metadata.size);
                     let woffset = write_metadata.offset + res;
@@ -305,10 +709,21 @@ pub fn execute_dd(arg_data: &ArgData) -> std::io::Result<u64> {
                     
                     metadata[buf_idx as usize * 2 + 1] = RWMetadata { offset: woffset, size: wsize };
                 } else {
+                    // One record out per block: full only if the whole block,
+                    // across any short-write retries, came to `bs` bytes.
+                    let block_start = obase + metadata[buf_idx as usize * 2].offset - ibase;
+                    if write_metadata.offset + res - block_start == bs {
+                        stats.full_writes += 1;
+                    } else {
+                        stats.partial_writes += 1;
+                    }
                     cur_blocks += 1;
                     free_bufs.push_back(buf_idx);
                 }
             }
+            if STATUS_REQUESTED.swap(false, Ordering::Relaxed) {
+                print_transfer_status(status_byte_count.load(Ordering::Relaxed), start_time);
+            }
             if uring.completion().is_empty() {
                 break;
             }
@@ -317,23 +732,207 @@ pub fn execute_dd(arg_data: &ArgData) -> std::io::Result<u64> {
             uring.submit()?;
         }
     }
-    
+
     uring.submitter().unregister_buffers()?;
     uring.submitter().unregister_files()?;
+
+    Ok(stats)
+}
+
+pub fn execute_dd(arg_data: &ArgData) -> std::io::Result<u64> {
+    install_status_signal_handlers();
+    let start_time = std::time::Instant::now();
+    let ifile = &arg_data.ifile;
+    let ofile = &arg_data.ofile;
+    let bs = arg_data.block_size as u64;
+    let ibase = arg_data.iseek * bs;
+    let obase = arg_data.oseek * bs;
+    let file_len = ifile.metadata()?.len().checked_sub(ibase)
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::Other, "Invalid input seek offset"))?;
+    let total_size = match arg_data.count {
+        Some(c) => file_len.min(c * bs),
+        None => file_len,
+    };
+    if arg_data.direct {
+        validate_direct_alignment(bs, ibase, obase, total_size)?;
+    }
+    let num_blocks = if total_size % bs == 0 {total_size / bs} else {total_size / bs + 1};
+
+    let status_byte_count = Arc::new(AtomicU64::new(0));
+    let status_byte_count_clone = Arc::clone(&status_byte_count);
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let stop_flag_clone = Arc::clone(&stop_flag);
+    let handle = if !arg_data.progress {
+        None
+    } else {
+        Some(std::thread::spawn(move || {
+            while !stop_flag_clone.load(Ordering::Relaxed) {
+                let bytes_copied = status_byte_count_clone.load(Ordering::Relaxed);
+                print_status(bytes_copied, total_size);
+                std::thread::sleep(std::time::Duration::from_secs(1));
+            }
+        }))
+    };
+
+    let algo = arg_data.hash.or(if arg_data.verify { Some(HashAlgo::Md5) } else { None });
+
+    let mut total_stats = WorkerStats::default();
+
+    let jobs = arg_data.jobs.max(1);
+    if jobs <= 1 {
+        let reads: VecDeque<(u64, u64)> = chunk_to_reads((0, num_blocks), bs, ibase, total_size).into();
+        let stats = run_copy_worker(CopyWorkerArgs {
+            ifile: ifile.try_clone()?,
+            ofile: ofile.try_clone()?,
+            bs,
+            ibase,
+            obase,
+            reads,
+            num_buffers: arg_data.num_buffers,
+            direct: arg_data.direct,
+            conv: arg_data.conv.clone(),
+            memlock_divisor: 1,
+            status_byte_count: Arc::clone(&status_byte_count),
+            start_time,
+        })?;
+        total_stats.bytes_written += stats.bytes_written;
+        total_stats.full_reads += stats.full_reads;
+        total_stats.partial_reads += stats.partial_reads;
+        total_stats.full_writes += stats.full_writes;
+        total_stats.partial_writes += stats.partial_writes;
+    } else {
+        let chunks = build_chunks(num_blocks, jobs);
+        let mut worker_chunks: Vec<Vec<(u64, u64)>> = vec![Vec::new(); jobs as usize];
+        for (i, chunk) in chunks.into_iter().enumerate() {
+            worker_chunks[i % jobs as usize].push(chunk);
+        }
+        let worker_num_buffers = (arg_data.num_buffers / jobs).max(1);
+
+        let mut handles = Vec::new();
+        for chunks in worker_chunks {
+            if chunks.is_empty() {
+                continue;
+            }
+            let mut reads = VecDeque::new();
+            for chunk in chunks {
+                reads.extend(chunk_to_reads(chunk, bs, ibase, total_size));
+            }
+            let worker = CopyWorkerArgs {
+                ifile: ifile.try_clone()?,
+                ofile: ofile.try_clone()?,
+                bs,
+                ibase,
+                obase,
+                reads,
+                num_buffers: worker_num_buffers,
+                direct: arg_data.direct,
+                conv: arg_data.conv.clone(),
+                memlock_divisor: jobs,
+                status_byte_count: Arc::clone(&status_byte_count),
+                start_time,
+            };
+            handles.push(std::thread::spawn(move || run_copy_worker(worker)));
+        }
+        for handle in handles {
+            let stats = handle.join().expect("copy worker thread panicked")?;
+            total_stats.bytes_written += stats.bytes_written;
+            total_stats.full_reads += stats.full_reads;
+            total_stats.partial_reads += stats.partial_reads;
+            total_stats.full_writes += stats.full_writes;
+            total_stats.partial_writes += stats.partial_writes;
+        }
+    }
+
     stop_flag.store(true, Ordering::Relaxed);
     if let Some(handle) = handle {
         handle.join().expect("Failed to join progress thread");
         eprintln!("\rProgress: 100.00% done");
     }
-    
+
+    if let Some(algo) = algo {
+        let digest_hex = hash_output_range(ofile, obase, total_size, bs, algo, arg_data.direct)?;
+        eprintln!("{} checksum: {} ({} bytes)", hash_algo_name(algo), digest_hex, total_size);
+
+        if arg_data.verify {
+            let verify_hex = hash_output_range(ofile, obase, total_size, bs, algo, arg_data.direct)?;
+            if verify_hex != digest_hex {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!(
+                        "verification failed: a second read-back of the output produced digest {} but the first produced {}",
+                        verify_hex, digest_hex
+                    ),
+                ));
+            }
+            eprintln!("Verify OK: re-read of output matches ({} {})", hash_algo_name(algo), digest_hex);
+        }
+    }
+
+    let elapsed = start_time.elapsed().as_secs_f64();
+    let mb_per_sec = if elapsed > 0.0 { (total_size as f64 / 1_000_000.0) / elapsed } else { 0.0 };
+    eprintln!("{}+{} records in", total_stats.full_reads, total_stats.partial_reads);
+    eprintln!("{}+{} records out", total_stats.full_writes, total_stats.partial_writes);
+    eprintln!("{} bytes copied, {:.1} s, {:.2} MB/s", total_size, elapsed, mb_per_sec);
+
     Ok(total_size)
 }
 
+/// Reads `ofile` back from `obase` through a dedicated ring and hashes it in
+/// one single-threaded, bounded-memory pass. `--hash` calls this once the
+/// copy completes; `--verify` calls it again and compares the two digests.
+/// An earlier design tried to fold the hash from each worker's write
+/// completions as they streamed in, but write CQEs (especially shuffled
+/// across `--jobs` workers, or skipped by `conv=noerror`) don't arrive in a
+/// contiguous order it could count on, and buffering out-of-order writes
+/// until they did was both unbounded and, once capped, deadlock-prone (the
+/// worker that owns the next contiguous region could itself be the one
+/// blocked waiting for room). Hashing the finished file in one pass
+/// sidesteps all of that. When `direct` is set, `ofile` was opened with
+/// O_DIRECT, so the read buffer must be aligned the same way the copy's own
+/// buffers are; `validate_direct_alignment` already guarantees `total_size`
+/// is a multiple of `bs`, so every read here is a full, aligned block and
+/// there is no unaligned tail to special-case.
+fn hash_output_range(ofile: &std::fs::File, obase: u64, total_size: u64, bs: u64, algo: HashAlgo, direct: bool) -> std::io::Result<String> {
+    let mut uring = IoUring::new(64)?;
+    let mut state = ChecksumState::new(algo);
+    let buf_align = if direct { DIRECT_ALIGN } else { 1 };
+    let mut buf = AlignedBuf::new(bs as usize, buf_align)?;
+    let mut offset = 0u64;
+    while offset < total_size {
+        let size = bs.min(total_size - offset);
+        let read_op = opcode::Read::new(types::Fd(ofile.as_raw_fd()), buf.as_mut_ptr(), size as u32)
+            .offset(obase + offset)
+            .build()
+            .user_data(0);
+        unsafe {
+            uring.submission().push(&read_op)
+                .map_err(|_| std::io::Error::new(std::io::ErrorKind::Other, "submission queue full"))?;
+        }
+        uring.submit_and_wait(1)?;
+        let cqe = uring.completion().next().expect("Failed to get completion event");
+        let res = cqe.result();
+        if res < 0 {
+            return Err(std::io::Error::from_raw_os_error(-res as i32));
+        }
+        let res = res as u64;
+        if res == 0 {
+            // EOF short of total_size: a conv=noerror drop can leave the
+            // output shorter than the nominal transfer size. Hash whatever
+            // actually landed instead of spinning forever waiting for bytes
+            // that were never written — --hash and --verify each see the
+            // same real file state either way, so they still agree.
+            break;
+        }
+        state.update(&buf[..res as usize]);
+        offset += res;
+    }
+    Ok(state.finalize_hex())
+}
 
 pub fn arg_parse() -> ArgData {
     let args = Args::parse();
-    let input_file = open_file(&args.input_file).expect("Failed to open input file");
-    let output_file = open_file(&args.output_file).expect("Failed to open output file");
+    let input_file = open_file(&args.input_file, args.direct).expect("Failed to open input file");
+    let output_file = open_file(&args.output_file, args.direct).expect("Failed to open output file");
     let (ring_size, num_buffers) = match (args.ring_size, args.num_buffers) {
         (Some(r), Some(n)) => {
             if r == 0 || n == 0 {
@@ -360,15 +959,52 @@ pub fn arg_parse() -> ArgData {
         panic!("Block size must be greater than 0");
     }
 
+    if args.jobs == 0 {
+        panic!("Number of jobs must be greater than 0");
+    }
+
+    let count = args.count.map(|c| match c {
+        CountSpec::Blocks(blocks) => blocks,
+        CountSpec::Bytes(bytes) => bytes.div_ceil(args.block_size as u64),
+    });
+
     ArgData {
         ifile: input_file,
         ofile: output_file,
         block_size: args.block_size,
-        count: args.count,
+        count,
         iseek: args.input_seek,
         oseek: args.output_seek,
         ring_size: ring_size,
         num_buffers: num_buffers,
         progress: args.progress,
+        direct: args.direct,
+        conv: args.conv,
+        jobs: args.jobs,
+        hash: args.hash,
+        verify: args.verify,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_size_accepts_decimal_and_binary_suffixes() {
+        assert_eq!(parse_size("512").unwrap(), 512);
+        assert_eq!(parse_size("4K").unwrap(), 4096);
+        assert_eq!(parse_size("4KiB").unwrap(), 4096);
+        assert_eq!(parse_size("1kB").unwrap(), 1_000);
+        assert_eq!(parse_size("2M").unwrap(), 2 * 1024 * 1024);
+        assert_eq!(parse_size("2MB").unwrap(), 2_000_000);
+        assert_eq!(parse_size("1GiB").unwrap(), 1024 * 1024 * 1024);
+        assert!(parse_size("4 Qi").is_err());
+    }
+
+    #[test]
+    fn parse_count_distinguishes_blocks_from_byte_counts() {
+        assert!(matches!(parse_count("10").unwrap(), CountSpec::Blocks(10)));
+        assert!(matches!(parse_count("10MiB").unwrap(), CountSpec::Bytes(n) if n == 10 * 1024 * 1024));
     }
 }
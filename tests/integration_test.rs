@@ -1,5 +1,30 @@
+use fastdd::{ArgData, ConvMode, HashAlgo};
 use rand::Rng;
 
+fn base_args(src: &std::path::Path, dst: &std::path::Path) -> anyhow::Result<ArgData> {
+    base_args_with_ifile(std::fs::File::open(src)?, dst)
+}
+
+fn base_args_with_ifile(ifile: std::fs::File, dst: &std::path::Path) -> anyhow::Result<ArgData> {
+    Ok(ArgData {
+        ifile,
+        // Opened read-write like `open_file` does: --hash/--verify read the output back.
+        ofile: std::fs::OpenOptions::new().read(true).write(true).create(true).truncate(true).open(dst)?,
+        block_size: 4096,
+        count: None,
+        iseek: 0,
+        oseek: 0,
+        ring_size: 256,
+        num_buffers: 128,
+        progress: false,
+        direct: false,
+        conv: vec![],
+        jobs: 1,
+        hash: None,
+        verify: false,
+    })
+}
+
 #[test]
 fn copy_random_5m() -> anyhow::Result<()> {
     let tmp = tempfile::tempdir()?;
@@ -9,23 +34,185 @@ fn copy_random_5m() -> anyhow::Result<()> {
     let mut rng = rand::rng();
     let data: Vec<u8> = (0..5 * 1024 * 1024).map(|_| rng.random::<u8>()).collect();
     std::fs::write(&src, &*data)?;
-    
-    let argdata = fastdd::ArgData {
-        ifile: std::fs::File::open(&src)?,
-        ofile: std::fs::File::create(&dst)?,
-        block_size: 4096,
-        count: None,
-        iseek: 0,
-        oseek: 0,
-        ring_size: 256,
-        num_buffers: 128,
-    };
+
+    let argdata = base_args(&src, &dst)?;
 
     let result = fastdd::execute_dd(&argdata);
     assert!(result.is_ok());
     assert_eq!(result.unwrap(), 5 * 1024 * 1024);
-    
+
+    assert_eq!(md5::compute(std::fs::read(src)?),
+               md5::compute(std::fs::read(dst)?));
+    Ok(())
+}
+
+#[test]
+fn conv_ucase_uppercases_copied_data() -> anyhow::Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let src = tmp.path().join("src.txt");
+    let dst = tmp.path().join("dst.txt");
+
+    std::fs::write(&src, b"the quick brown fox")?;
+
+    let mut argdata = base_args(&src, &dst)?;
+    argdata.conv = vec![ConvMode::Ucase];
+
+    fastdd::execute_dd(&argdata)?;
+
+    assert_eq!(std::fs::read(dst)?, b"THE QUICK BROWN FOX");
+    Ok(())
+}
+
+#[test]
+fn conv_swab_swaps_adjacent_byte_pairs() -> anyhow::Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let src = tmp.path().join("src.bin");
+    let dst = tmp.path().join("dst.bin");
+
+    std::fs::write(&src, [1u8, 2, 3, 4, 5, 6])?;
+
+    let mut argdata = base_args(&src, &dst)?;
+    argdata.conv = vec![ConvMode::Swab];
+
+    fastdd::execute_dd(&argdata)?;
+
+    assert_eq!(std::fs::read(dst)?, vec![2u8, 1, 4, 3, 6, 5]);
+    Ok(())
+}
+
+#[test]
+fn conv_sync_zero_pads_short_final_block() -> anyhow::Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let src = tmp.path().join("src.bin");
+    let dst = tmp.path().join("dst.bin");
+
+    // Not a multiple of the 4096-byte block size, so the last block read is short.
+    let data = vec![0xABu8; 4096 + 100];
+    std::fs::write(&src, &data)?;
+
+    let mut argdata = base_args(&src, &dst)?;
+    argdata.conv = vec![ConvMode::Sync];
+
+    fastdd::execute_dd(&argdata)?;
+
+    let out = std::fs::read(&dst)?;
+    assert_eq!(out.len(), 2 * 4096);
+    assert_eq!(&out[..data.len()], &data[..]);
+    assert!(out[data.len()..].iter().all(|&b| b == 0));
+    Ok(())
+}
+
+#[test]
+fn conv_noerror_does_not_disturb_an_error_free_copy() -> anyhow::Result<()> {
+    // conv=noerror only changes behavior on a failed read; regular files don't
+    // give us an easy way to inject one, so this just checks the flag is inert
+    // when nothing actually goes wrong.
+    let tmp = tempfile::tempdir()?;
+    let src = tmp.path().join("src.bin");
+    let dst = tmp.path().join("dst.bin");
+
+    std::fs::write(&src, vec![0x42u8; 4096 * 3])?;
+
+    let mut argdata = base_args(&src, &dst)?;
+    argdata.conv = vec![ConvMode::NoError];
+
+    fastdd::execute_dd(&argdata)?;
+
+    assert_eq!(std::fs::read(src)?, std::fs::read(dst)?);
+    Ok(())
+}
+
+/// Reading a directory's fd fails with EISDIR on Linux, which gives us a
+/// real, portable read(2) failure to inject without a special device or a
+/// race — unlike a dropped socket or pipe, it fails on every attempt rather
+/// than returning EOF.
+fn open_unreadable_region(tmp: &std::path::Path) -> std::io::Result<std::fs::File> {
+    let dir = tmp.join("unreadable_dir");
+    std::fs::create_dir(&dir)?;
+    std::fs::File::open(&dir)
+}
+
+#[test]
+fn conv_noerror_skips_an_unreadable_region_without_aborting() -> anyhow::Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let dst = tmp.path().join("dst.bin");
+
+    let ifile = open_unreadable_region(tmp.path())?;
+    let size = ifile.metadata()?.len();
+    assert!(size > 0, "a directory's reported size should be nonzero");
+
+    let mut argdata = base_args_with_ifile(ifile, &dst)?;
+    argdata.block_size = size as usize;
+    argdata.conv = vec![ConvMode::NoError];
+
+    // Without conv=noerror this read failure would abort the copy; with it,
+    // the unreadable region is dropped and the copy still reports success.
+    let result = fastdd::execute_dd(&argdata);
+    assert!(result.is_ok());
+    assert_eq!(std::fs::read(dst)?.len(), 0, "the dropped region should never be written");
+    Ok(())
+}
+
+#[test]
+fn conv_noerror_with_hash_and_verify_does_not_spuriously_fail() -> anyhow::Result<()> {
+    // A region dropped by conv=noerror is never written, so --hash/--verify
+    // must not try to fold it into an in-flight digest that can never reach
+    // that offset; they hash the finished (partial) output, which always
+    // succeeds regardless of what noerror skipped.
+    let tmp = tempfile::tempdir()?;
+    let dst = tmp.path().join("dst.bin");
+
+    let ifile = open_unreadable_region(tmp.path())?;
+    let size = ifile.metadata()?.len();
+
+    let mut argdata = base_args_with_ifile(ifile, &dst)?;
+    argdata.block_size = size as usize;
+    argdata.conv = vec![ConvMode::NoError];
+    argdata.hash = Some(HashAlgo::Sha256);
+    argdata.verify = true;
+
+    let result = fastdd::execute_dd(&argdata);
+    assert!(result.is_ok());
+    Ok(())
+}
+
+#[test]
+fn jobs_parallel_copy_matches_serial() -> anyhow::Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let src = tmp.path().join("src.bin");
+    let dst = tmp.path().join("dst.bin");
+
+    let mut rng = rand::rng();
+    let data: Vec<u8> = (0..2 * 1024 * 1024).map(|_| rng.random::<u8>()).collect();
+    std::fs::write(&src, &*data)?;
+
+    let mut argdata = base_args(&src, &dst)?;
+    argdata.jobs = 4;
+
+    let result = fastdd::execute_dd(&argdata)?;
+    assert_eq!(result, data.len() as u64);
+    assert_eq!(md5::compute(std::fs::read(src)?),
+               md5::compute(std::fs::read(dst)?));
+    Ok(())
+}
+
+#[test]
+fn hash_and_verify_confirm_output_matches_input() -> anyhow::Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let src = tmp.path().join("src.bin");
+    let dst = tmp.path().join("dst.bin");
+
+    let mut rng = rand::rng();
+    let data: Vec<u8> = (0..1024 * 1024).map(|_| rng.random::<u8>()).collect();
+    std::fs::write(&src, &*data)?;
+
+    let mut argdata = base_args(&src, &dst)?;
+    argdata.hash = Some(HashAlgo::Sha256);
+    argdata.verify = true;
+
+    let result = fastdd::execute_dd(&argdata);
+    assert!(result.is_ok());
     assert_eq!(md5::compute(std::fs::read(src)?),
                md5::compute(std::fs::read(dst)?));
     Ok(())
-}
\ No newline at end of file
+}